@@ -0,0 +1,256 @@
+//! Alternate play mode: instead of rolling the `Ball` through the maze by tilting it,
+//! you walk a capsule through it on foot from a trailing camera, the way
+//! `avian3d`'s own `dynamic_character_3d` example drives a character controller.
+//!
+//! [`plugin`] composes with [`crate::plugin`] (for `DefaultPlugins`, physics, `setup`
+//! and the camera) rather than replacing it, and gates off [`crate::spawn_ball`] by
+//! inserting [`WalkingMode`] so the capsule from [`spawn_character`] is the only thing
+//! that spawns on `BallStart`. It still reuses [`crate::adjust_camera`] for dolly/zoom,
+//! adding only [`camera_follow`] to keep the camera trailing the character instead of
+//! orbiting a fixed point.
+
+use bevy::{
+    app::{App, Update},
+    asset::Assets,
+    color::Color,
+    ecs::{
+        component::Component,
+        entity::Entity,
+        error::Result,
+        query::{Added, With, Without},
+        resource::Resource,
+        schedule::IntoScheduleConfigs,
+        system::{Commands, Query, Res, ResMut},
+    },
+    input::{
+        ButtonInput,
+        gamepad::{Gamepad, GamepadAxis, GamepadButton},
+        keyboard::KeyCode,
+    },
+    math::{Dir3, Quat, Vec3, primitives::Capsule3d},
+    pbr::{MeshMaterial3d, StandardMaterial},
+    render::{
+        camera::Camera,
+        mesh::{Mesh, Mesh3d},
+    },
+    transform::components::{GlobalTransform, Transform},
+};
+
+use avian3d::{
+    collision::collider::Collider,
+    dynamics::rigid_body::{LinearVelocity, LockedAxes, RigidBody},
+    spatial_query::{ShapeCastConfig, ShapeCaster, ShapeHits, SpatialQuery, SpatialQueryFilter},
+};
+
+use crate::{ANALOG_THRESHOLD, BallStart};
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins(crate::plugin)
+        .insert_resource(WalkingMode)
+        .init_resource::<GlobalStep>()
+        .init_resource::<CameraAnchor>()
+        .add_systems(Update, spawn_character)
+        .add_systems(
+            Update,
+            (update_on_ground, character_movement, climb_steps).chain(),
+        )
+        .add_systems(Update, camera_follow.after(crate::adjust_camera));
+}
+
+/// Inserted by [`plugin`] so `crate::spawn_ball` skips spawning the rolling `Ball`
+/// once a walking session has taken over `BallStart` with [`spawn_character`] instead.
+#[derive(Resource)]
+pub(crate) struct WalkingMode;
+
+const CHARACTER_RADIUS: f32 = 0.4;
+const CHARACTER_LENGTH: f32 = 0.6;
+const GROUND_CHECK_DISTANCE: f32 = 0.2;
+const MOVE_SPEED: f32 = 6.;
+const JUMP_SPEED: f32 = 6.;
+
+/// How high a ledge can be before the character auto-climbs it instead of being
+/// blocked by it, like a stair rather than a wall.
+#[derive(Resource, Clone, Copy)]
+pub struct GlobalStep {
+    pub max_height: f32,
+}
+
+impl Default for GlobalStep {
+    fn default() -> Self {
+        Self { max_height: 0.35 }
+    }
+}
+
+#[derive(Component, Default)]
+struct CharacterController {
+    on_ground: bool,
+    jump: bool,
+}
+
+fn spawn_character(
+    mut commands: Commands,
+    start: Query<&GlobalTransform, Added<BallStart>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for start in start {
+        let ground_caster_shape = Collider::capsule(CHARACTER_RADIUS * 0.99, CHARACTER_LENGTH);
+        commands.spawn((
+            CharacterController::default(),
+            RigidBody::Dynamic,
+            Collider::capsule(CHARACTER_RADIUS, CHARACTER_LENGTH),
+            LockedAxes::ROTATION_LOCKED,
+            ShapeCaster::new(ground_caster_shape, Vec3::ZERO, Quat::IDENTITY, Dir3::NEG_Y)
+                .with_max_distance(GROUND_CHECK_DISTANCE),
+            start.compute_transform(),
+            MeshMaterial3d(materials.add(StandardMaterial::from(Color::srgb(0.4, 0.1, 0.1)))),
+            Mesh3d(meshes.add(Capsule3d::new(CHARACTER_RADIUS, CHARACTER_LENGTH))),
+        ));
+    }
+}
+
+fn update_on_ground(mut characters: Query<(&mut CharacterController, &ShapeHits)>) {
+    for (mut controller, hits) in &mut characters {
+        controller.on_ground = !hits.is_empty();
+    }
+}
+
+fn character_movement(
+    gamepads: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    camera: Query<&Transform, With<Camera>>,
+    mut characters: Query<(&mut CharacterController, &mut LinearVelocity)>,
+) -> Result {
+    let camera_transform = camera.single()?;
+    let forward = Vec3::new(camera_transform.forward().x, 0., camera_transform.forward().z)
+        .normalize_or_zero();
+    let right =
+        Vec3::new(camera_transform.right().x, 0., camera_transform.right().z).normalize_or_zero();
+
+    let mut stick_x = 0.;
+    let mut stick_y = 0.;
+    for gp in &gamepads {
+        let analog = gp.analog();
+        let x = analog.get(GamepadAxis::LeftStickX).unwrap_or_default();
+        let y = analog.get(GamepadAxis::LeftStickY).unwrap_or_default();
+        if x.abs() > ANALOG_THRESHOLD || y.abs() > ANALOG_THRESHOLD {
+            stick_x = x;
+            stick_y = y;
+        }
+    }
+    if keys.pressed(KeyCode::KeyA) || keys.pressed(KeyCode::ArrowLeft) {
+        stick_x -= 1.;
+    }
+    if keys.pressed(KeyCode::KeyD) || keys.pressed(KeyCode::ArrowRight) {
+        stick_x += 1.;
+    }
+    if keys.pressed(KeyCode::KeyS) || keys.pressed(KeyCode::ArrowDown) {
+        stick_y -= 1.;
+    }
+    if keys.pressed(KeyCode::KeyW) || keys.pressed(KeyCode::ArrowUp) {
+        stick_y += 1.;
+    }
+
+    let jump_pressed = keys.just_pressed(KeyCode::Space)
+        || gamepads.iter().any(|gp| gp.just_pressed(GamepadButton::South));
+
+    for (mut controller, mut velocity) in &mut characters {
+        let direction = (forward * stick_y + right * stick_x).clamp_length_max(1.);
+        velocity.x = direction.x * MOVE_SPEED;
+        velocity.z = direction.z * MOVE_SPEED;
+
+        // Jumps are queued rather than applied immediately, so a press slightly before
+        // landing isn't dropped; it fires as soon as `on_ground` becomes true.
+        if jump_pressed {
+            controller.jump = true;
+        }
+        if controller.jump && controller.on_ground {
+            velocity.y = JUMP_SPEED;
+            controller.jump = false;
+        }
+    }
+    Ok(())
+}
+
+/// Auto-climbs ledges up to [`GlobalStep::max_height`]: if the character's horizontal
+/// movement is blocked at foot height but the same shapecast is clear once raised by
+/// the step height, it's a ledge rather than a wall, so the capsule is snapped up onto
+/// it instead of stopping.
+fn climb_steps(
+    step: Res<GlobalStep>,
+    spatial_query: SpatialQuery,
+    mut characters: Query<(Entity, &mut Transform, &LinearVelocity, &CharacterController), Without<Camera>>,
+) {
+    for (entity, mut transform, velocity, controller) in &mut characters {
+        if !controller.on_ground {
+            continue;
+        }
+        let horizontal = Vec3::new(velocity.x, 0., velocity.z);
+        let Ok(direction) = Dir3::new(horizontal) else {
+            continue;
+        };
+
+        let filter = SpatialQueryFilter::default().with_excluded_entities([entity]);
+        let probe_radius = CHARACTER_RADIUS * 0.9;
+        let cast_shape = Collider::sphere(probe_radius);
+        let config = ShapeCastConfig::from_max_distance(CHARACTER_RADIUS + 0.1);
+
+        // The probe's bottom sits FOOT_CLEARANCE above the capsule's lowest point, so
+        // both casts and the measured step height are relative to the character's feet.
+        const FOOT_CLEARANCE: f32 = 0.05;
+        let foot_origin = transform.translation
+            - Vec3::Y * (CHARACTER_LENGTH / 2. + CHARACTER_RADIUS - probe_radius - FOOT_CLEARANCE);
+        let blocked_at_feet = spatial_query
+            .cast_shape(&cast_shape, foot_origin, Quat::IDENTITY, direction, &config, &filter)
+            .is_some();
+        if !blocked_at_feet {
+            continue;
+        }
+
+        let raised_origin = foot_origin + Vec3::Y * step.max_height;
+        let clear_above_step = spatial_query
+            .cast_shape(&cast_shape, raised_origin, Quat::IDENTITY, direction, &config, &filter)
+            .is_none();
+        if !clear_above_step {
+            continue;
+        }
+
+        // It's a ledge, not a wall: find how far down the step actually is from the
+        // raised probe instead of snapping the full max_height regardless of the
+        // real ledge height.
+        let down_config = ShapeCastConfig::from_max_distance(step.max_height);
+        if let Some(hit) = spatial_query.cast_shape(
+            &cast_shape,
+            raised_origin,
+            Quat::IDENTITY,
+            Dir3::NEG_Y,
+            &down_config,
+            &filter,
+        ) {
+            transform.translation.y += step.max_height - hit.distance + FOOT_CLEARANCE;
+        }
+    }
+}
+
+/// The point the camera is currently orbiting, tracked across frames so
+/// [`crate::adjust_camera`]'s dolly/zoom (which moves the camera along its own forward
+/// vector) keeps working unmodified: each frame the camera's offset from the previous
+/// anchor is re-applied relative to the character's new position.
+#[derive(Resource, Default)]
+struct CameraAnchor(Vec3);
+
+fn camera_follow(
+    characters: Query<&Transform, (With<CharacterController>, Without<Camera>)>,
+    mut camera: Query<&mut Transform, With<Camera>>,
+    mut anchor: ResMut<CameraAnchor>,
+) -> Result {
+    let character = characters.single()?;
+    let mut camera_transform = camera.single_mut()?;
+
+    let offset = camera_transform.translation - anchor.0;
+    camera_transform.translation = character.translation + offset;
+    camera_transform.look_at(character.translation, Vec3::Y);
+    anchor.0 = character.translation;
+
+    Ok(())
+}
@@ -1,15 +1,19 @@
-use std::f32::consts::{FRAC_PI_2, FRAC_PI_6};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_6, PI};
 
 use bevy::{
     DefaultPlugins,
     app::{App, Startup, Update},
-    asset::Assets,
+    asset::{Assets, RenderAssetUsages},
     color::Color,
     core_pipeline::core_3d::Camera3d,
     ecs::{
         component::Component,
+        entity::Entity,
         error::Result,
+        event::{Event, EventReader, EventWriter},
         query::{Added, Changed, With, Without},
+        resource::Resource,
+        schedule::IntoScheduleConfigs,
         system::{Commands, Query, Res, ResMut},
     },
     input::gamepad::{Gamepad, GamepadAxis, GamepadButton},
@@ -20,7 +24,7 @@ use bevy::{
     pbr::{DirectionalLight, MeshMaterial3d, StandardMaterial},
     render::{
         camera::{Camera, PerspectiveProjection, Projection},
-        mesh::{Mesh, Mesh3d},
+        mesh::{Indices, Mesh, Mesh3d, PrimitiveTopology},
         view::Visibility,
     },
     time::Time,
@@ -29,23 +33,80 @@ use bevy::{
 
 use avian3d::{
     PhysicsPlugins,
-    collision::collider::Collider,
+    collision::{
+        collider::{Collider, Sensor},
+        collision_events::CollisionStarted,
+    },
     dynamics::rigid_body::{
         AngularVelocity, CoefficientCombine, LinearVelocity, Restitution, RigidBody,
     },
 };
 
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+pub mod character;
+pub mod net;
+
 pub fn plugin(app: &mut App) {
     app.add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+        .init_resource::<MazeConfig>()
+        .init_resource::<TerrainConfig>()
+        .init_resource::<RunTimer>()
+        .init_resource::<PlayerAssignment>()
+        .add_event::<MazeSolved>()
         .add_systems(Startup, setup)
-        .add_systems(Update, maze_attitude)
-        .add_systems(Update, spawn_ball)
-        .add_systems(Update, reset_ball)
-        .add_systems(Update, adjust_camera);
+        .add_systems(
+            Update,
+            assign_players.before(maze_attitude).before(adjust_camera),
+        )
+        .add_systems(Update, maze_attitude.run_if(netplay_mode_inactive))
+        .add_systems(Update, spawn_ball.run_if(ball_mode_active))
+        .add_systems(Update, reset_ball.run_if(netplay_mode_inactive))
+        .add_systems(Update, adjust_camera)
+        .add_systems(Update, tick_run_timer)
+        .add_systems(Update, goal_collision);
+}
+
+/// Difficulty knobs for [`generate_maze`]. `seed` is fixed by default so repeated
+/// runs reproduce the same layout; change it (or randomize it before inserting the
+/// resource) to get a different maze.
+#[derive(Resource, Clone, Copy)]
+pub struct MazeConfig {
+    pub cells_w: u32,
+    pub cells_h: u32,
+    pub seed: u64,
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        Self {
+            cells_w: 6,
+            cells_h: 6,
+            seed: 0,
+        }
+    }
+}
+
+/// Shape of the floor's procedural terrain, built as a `segments × segments` grid
+/// sampling [`terrain_height`]. An `amplitude` of `0.` keeps the floor perfectly flat,
+/// matching the original `Cuboid` floor; raise it for a gently sloped board.
+#[derive(Resource, Clone, Copy)]
+pub struct TerrainConfig {
+    pub segments: u32,
+    pub amplitude: f32,
+}
+
+impl Default for TerrainConfig {
+    fn default() -> Self {
+        Self {
+            segments: 32,
+            amplitude: 0.6,
+        }
+    }
 }
 
 #[derive(Component)]
-struct Ball;
+pub(crate) struct Ball;
 
 const THICKNESS: f32 = 0.1;
 const WIDTH: f32 = 2.;
@@ -53,47 +114,48 @@ const WALL_Y: f32 = WIDTH / 2.;
 const WALL_RESTITUTION: f32 = 1.;
 
 #[derive(Component)]
-struct BallStart;
+pub(crate) struct BallStart;
+
+#[derive(Component)]
+pub(crate) struct Maze;
 
 #[derive(Component)]
-struct Maze;
+struct Goal;
+
+/// Elapsed time for the ball's current attempt at solving the maze. Starts ticking
+/// when the ball first spawns and resets alongside it when `Start` is pressed.
+#[derive(Resource, Default)]
+struct RunTimer {
+    elapsed_secs: f32,
+    running: bool,
+}
+
+/// Fired when the ball reaches the [`Goal`], carrying the solve time in seconds.
+#[derive(Event)]
+pub struct MazeSolved {
+    pub secs: f32,
+}
 
 const BALL_RADIUS: f32 = 0.8;
 const BALL_START_ELEVATION: f32 = (THICKNESS / 2.) + BALL_RADIUS;
+const GOAL_RADIUS: f32 = 0.6;
 fn setup(
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
+    maze_config: Res<MazeConfig>,
+    terrain_config: Res<TerrainConfig>,
 ) -> Result {
-    #[expect(unused)]
-    const FULL_Z_ROW: &str = ".-.-.-.-.-.-.";
-    #[expect(unused)]
-    const FULL_X_ROW: &str = "| | | | | | |";
-    #[expect(unused)]
-    const EMPTY_X_ROW: &str = "|           |";
-
-    const SAMPLE_MAZE: &[&str] = &[
-        ".-.-.-.-.-.-.",
-        "|x  |       |",
-        ".-. .-.-. .-.",
-        "|           |",
-        ". .-. .-.-. .",
-        "|   | |     |",
-        ".-.-. .-.-.-.",
-        "| |   |     |",
-        ". . .-.-. . .",
-        "|   |     | |",
-        ". .-. .-.-. .",
-        "|       |   |",
-        ".-.-.-.-. .-.",
-    ];
+    let maze = generate_maze(maze_config.cells_w, maze_config.cells_h, maze_config.seed);
 
     debug_assert_eq!(
-        SAMPLE_MAZE.len() % 2,
+        maze.len() % 2,
         1,
         "Maze should have odd number of rows because it should have Z walls on both ends"
     );
-    let z_offset = (SAMPLE_MAZE.len() / 2) as f32;
+    let z_offset = (maze.len() / 2) as f32;
+    let floor_width = maze.len() as f32;
+    let half_width = floor_width / 2.;
 
     let maze_rotation = Quat::from_axis_angle(Vec3::X, FRAC_PI_2);
     let maze_transform = Transform::from_rotation(maze_rotation);
@@ -107,6 +169,15 @@ fn setup(
     let z_wall_mesh = Mesh3d(meshes.add(Cuboid::from_size(Vec3::new(WIDTH, WIDTH, THICKNESS))));
     let wall_restitution = Restitution::new(WALL_RESTITUTION);
 
+    let (terrain_positions, terrain_indices) =
+        generate_terrain(floor_width, terrain_config.segments, terrain_config.amplitude);
+    let mut terrain_mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+    terrain_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, terrain_positions.clone());
+    terrain_mesh.insert_indices(Indices::U32(
+        terrain_indices.iter().flatten().copied().collect(),
+    ));
+    terrain_mesh.compute_normals();
+
     commands
         .spawn((
             RigidBody::Kinematic,
@@ -115,33 +186,42 @@ fn setup(
             Visibility::default(),
         ))
         .with_children(|spawner| {
-            for (zi, row) in SAMPLE_MAZE.iter().enumerate() {
+            for (zi, row) in maze.iter().enumerate() {
                 let zw = zi as f32 - z_offset;
                 let x_offset = (row.len() / 2) as f32;
 
                 for (xi, c) in row.chars().enumerate() {
                     let xw = xi as f32 - x_offset;
+                    let surface = terrain_height(xw, zw, half_width, terrain_config.amplitude);
                     match c {
                         '|' => {
                             spawner.spawn((
                                 x_wall_collider.clone(),
                                 wall_restitution,
-                                Transform::from_xyz(xw, WALL_Y, zw),
+                                Transform::from_xyz(xw, WALL_Y + surface, zw),
                                 maze_material.clone(),
                                 x_wall_mesh.clone(),
                             ));
                         }
                         'x' => {
                             spawner.spawn((
-                                Transform::from_xyz(xw, BALL_START_ELEVATION, zw),
+                                Transform::from_xyz(xw, BALL_START_ELEVATION + surface, zw),
                                 BallStart,
                             ));
                         }
+                        'o' => {
+                            spawner.spawn((
+                                Goal,
+                                Sensor,
+                                Collider::sphere(GOAL_RADIUS),
+                                Transform::from_xyz(xw, BALL_START_ELEVATION + surface, zw),
+                            ));
+                        }
                         '-' => {
                             spawner.spawn((
                                 z_wall_collider.clone(),
                                 wall_restitution,
-                                Transform::from_xyz(xw, WALL_Y, zw),
+                                Transform::from_xyz(xw, WALL_Y + surface, zw),
                                 maze_material.clone(),
                                 z_wall_mesh.clone(),
                             ));
@@ -150,16 +230,11 @@ fn setup(
                     }
                 }
             }
-            let floor_width = SAMPLE_MAZE.len() as f32;
             spawner.spawn((
-                Collider::cuboid(floor_width, THICKNESS, floor_width),
+                Collider::trimesh(terrain_positions, terrain_indices),
                 Restitution::new(0.).with_combine_rule(CoefficientCombine::Min),
                 maze_material,
-                Mesh3d(meshes.add(Cuboid::from_size(Vec3::new(
-                    floor_width,
-                    THICKNESS,
-                    floor_width,
-                )))),
+                Mesh3d(meshes.add(terrain_mesh)),
             ));
             spawner.spawn((
                 Collider::cuboid(floor_width, THICKNESS, floor_width),
@@ -186,11 +261,226 @@ fn setup(
     Ok(())
 }
 
-fn spawn_ball(
+/// Generates a perfect maze with a randomized depth-first "recursive backtracker" and
+/// renders it in the same ASCII wall-grid format `setup` parses: corners are `.`,
+/// `-`/`|` mark standing walls, a space means the wall between the two neighboring
+/// cells was knocked out, exactly one cell holds the `x` start, and the last border
+/// cell visited (so the exit wall next to it can be knocked out) holds the `o` goal.
+/// For example a `2x1` maze might render as:
+///
+/// ```text
+/// .-.-.
+/// |o x|
+/// . .-.
+/// ```
+///
+/// The returned rows are always `2 * cells_h + 1` long and `2 * cells_w + 1` wide, so
+/// the row count stays odd and the border is walled apart from that one exit, matching the
+/// `debug_assert_eq!(len % 2, 1)` invariant in `setup`. The same `seed` always
+/// produces the same maze.
+fn generate_maze(cells_w: u32, cells_h: u32, seed: u64) -> Vec<String> {
+    debug_assert!(cells_w > 0 && cells_h > 0, "maze must have at least one cell");
+    let (cells_w, cells_h) = (cells_w as usize, cells_h as usize);
+
+    #[derive(Clone, Copy, Default)]
+    struct Walls {
+        north: bool,
+        south: bool,
+        east: bool,
+        west: bool,
+    }
+
+    let idx = |x: usize, y: usize| y * cells_w + x;
+    let mut walls = vec![
+        Walls {
+            north: true,
+            south: true,
+            east: true,
+            west: true,
+        };
+        cells_w * cells_h
+    ];
+    let mut visited = vec![false; cells_w * cells_h];
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let is_border = |x: usize, y: usize| x == 0 || y == 0 || x == cells_w - 1 || y == cells_h - 1;
+
+    let start_cell = (rng.random_range(0..cells_w), rng.random_range(0..cells_h));
+    visited[idx(start_cell.0, start_cell.1)] = true;
+    let mut stack = vec![start_cell];
+    // Tracks the most recently visited border cell so the reserved exit always lands
+    // on the outer wall instead of (if the farthest-visited cell happened to be
+    // interior) punching a stray hole into the middle of the maze.
+    let mut last_border_cell = is_border(start_cell.0, start_cell.1).then_some(start_cell);
+
+    while let Some(&(cx, cy)) = stack.last() {
+        let mut unvisited = Vec::new();
+        if cy > 0 && !visited[idx(cx, cy - 1)] {
+            unvisited.push((cx, cy - 1));
+        }
+        if cy + 1 < cells_h && !visited[idx(cx, cy + 1)] {
+            unvisited.push((cx, cy + 1));
+        }
+        if cx > 0 && !visited[idx(cx - 1, cy)] {
+            unvisited.push((cx - 1, cy));
+        }
+        if cx + 1 < cells_w && !visited[idx(cx + 1, cy)] {
+            unvisited.push((cx + 1, cy));
+        }
+
+        let Some(&(nx, ny)) = unvisited.get(rng.random_range(0..unvisited.len().max(1))) else {
+            stack.pop();
+            continue;
+        };
+
+        if nx == cx + 1 {
+            walls[idx(cx, cy)].east = false;
+            walls[idx(nx, ny)].west = false;
+        } else if cx == nx + 1 {
+            walls[idx(cx, cy)].west = false;
+            walls[idx(nx, ny)].east = false;
+        } else if ny == cy + 1 {
+            walls[idx(cx, cy)].south = false;
+            walls[idx(nx, ny)].north = false;
+        } else {
+            walls[idx(cx, cy)].north = false;
+            walls[idx(nx, ny)].south = false;
+        }
+
+        visited[idx(nx, ny)] = true;
+        if is_border(nx, ny) {
+            last_border_cell = Some((nx, ny));
+        }
+        stack.push((nx, ny));
+    }
+
+    let (grid_w, grid_h) = (2 * cells_w + 1, 2 * cells_h + 1);
+    let mut grid = vec![vec![' '; grid_w]; grid_h];
+    for corner_y in (0..grid_h).step_by(2) {
+        for corner_x in (0..grid_w).step_by(2) {
+            grid[corner_y][corner_x] = '.';
+        }
+    }
+
+    for cy in 0..cells_h {
+        for cx in 0..cells_w {
+            let w = walls[idx(cx, cy)];
+            let (gx, gy) = (2 * cx + 1, 2 * cy + 1);
+            if w.north {
+                grid[gy - 1][gx] = '-';
+            }
+            if w.south {
+                grid[gy + 1][gx] = '-';
+            }
+            if w.west {
+                grid[gy][gx - 1] = '|';
+            }
+            if w.east {
+                grid[gy][gx + 1] = '|';
+            }
+        }
+    }
+
+    grid[2 * start_cell.1 + 1][2 * start_cell.0 + 1] = 'x';
+
+    // Reserve an exit by knocking out the border wall next to the last border cell
+    // visited (every grid has at least one, since cells_w/cells_h are both > 0).
+    let (ex, ey) = last_border_cell.expect("maze has at least one border cell");
+    let (gx, gy) = (2 * ex + 1, 2 * ey + 1);
+    if ex == cells_w - 1 {
+        grid[gy][gx + 1] = ' ';
+    } else if ey == cells_h - 1 {
+        grid[gy + 1][gx] = ' ';
+    } else if ex == 0 {
+        grid[gy][gx - 1] = ' ';
+    } else {
+        grid[gy - 1][gx] = ' ';
+    }
+
+    // The exit cell doubles as the goal, unless it's the single-cell maze's start.
+    if (ex, ey) != start_cell {
+        grid[gy][gx] = 'o';
+    }
+
+    grid.into_iter().map(|row| row.into_iter().collect()).collect()
+}
+
+/// Half-cosine bowl: flat at the center, rising smoothly to `amplitude` at the floor's
+/// rim. `0.` amplitude keeps the floor perfectly flat, matching the original `Cuboid`
+/// floor; this is the "height function" [`generate_terrain`] samples, so swap it for a
+/// ridge or another profile to change the board's shape.
+fn terrain_height(x: f32, z: f32, half_width: f32, amplitude: f32) -> f32 {
+    if half_width <= 0. {
+        return 0.;
+    }
+    let r = ((x * x + z * z).sqrt() / half_width).min(1.);
+    amplitude * 0.5 * (1. - (PI * r).cos())
+}
+
+/// Samples [`terrain_height`] across a `segments × segments` grid spanning `width` in
+/// both X and Z, returning the vertex positions and the two triangles per quad that
+/// tile them. `setup` builds both the render [`Mesh`] and the matching
+/// `Collider::trimesh` from this same data, so the ball can't fall through terrain its
+/// renderer doesn't show. Winding is counter-clockwise viewed from above so both the
+/// mesh and the collider's normals face up.
+fn generate_terrain(width: f32, segments: u32, amplitude: f32) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+    let segments = segments.max(1);
+    let verts_per_side = segments + 1;
+    let half_width = width / 2.;
+    let step = width / segments as f32;
+
+    let mut positions = Vec::with_capacity((verts_per_side * verts_per_side) as usize);
+    for zi in 0..verts_per_side {
+        let z = zi as f32 * step - half_width;
+        for xi in 0..verts_per_side {
+            let x = xi as f32 * step - half_width;
+            positions.push(Vec3::new(x, terrain_height(x, z, half_width, amplitude), z));
+        }
+    }
+
+    let idx = |xi: u32, zi: u32| zi * verts_per_side + xi;
+    let mut indices = Vec::with_capacity((segments * segments * 2) as usize);
+    for zi in 0..segments {
+        for xi in 0..segments {
+            let (a, b, c, d) = (
+                idx(xi, zi),
+                idx(xi + 1, zi),
+                idx(xi + 1, zi + 1),
+                idx(xi, zi + 1),
+            );
+            indices.push([a, d, b]);
+            indices.push([b, d, c]);
+        }
+    }
+
+    (positions, indices)
+}
+
+/// Lets [`spawn_ball`] skip spawning the rolling `Ball` once `character::plugin` has
+/// taken over `BallStart` with its own capsule and inserted [`character::WalkingMode`],
+/// or once `net::plugin` has taken over spawning it itself inside `FixedUpdate` and
+/// inserted [`net::NetplayMode`].
+fn ball_mode_active(
+    walking_mode: Option<Res<character::WalkingMode>>,
+    netplay_mode: Option<Res<net::NetplayMode>>,
+) -> bool {
+    walking_mode.is_none() && netplay_mode.is_none()
+}
+
+/// Lets [`maze_attitude`] and [`reset_ball`] skip their `Update`-scheduled work once
+/// `net::plugin` has inserted [`net::NetplayMode`] and taken over both inside its own
+/// `FixedUpdate` chain (`netplay_tilt` and a direct call to `reset_ball` itself), so the
+/// two copies don't fight over the same `Maze`/`Ball` every tick.
+fn netplay_mode_inactive(netplay_mode: Option<Res<net::NetplayMode>>) -> bool {
+    netplay_mode.is_none()
+}
+
+pub(crate) fn spawn_ball(
     mut commands: Commands,
     start: Query<&GlobalTransform, Added<BallStart>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut run_timer: ResMut<RunTimer>,
 ) -> Result {
     for start in start {
         // Ball
@@ -203,18 +493,98 @@ fn spawn_ball(
             MeshMaterial3d(materials.add(StandardMaterial::from(Color::srgb(0.4, 0.1, 0.1)))),
             Mesh3d(meshes.add(Sphere::new(BALL_RADIUS))),
         ));
+
+        run_timer.elapsed_secs = 0.;
+        run_timer.running = true;
     }
     Ok(())
 }
 
-const ANALOG_THRESHOLD: f32 = 0.1;
+fn tick_run_timer(mut run_timer: ResMut<RunTimer>, time: Res<Time>) {
+    if run_timer.running {
+        run_timer.elapsed_secs += time.delta_secs();
+    }
+}
+
+fn goal_collision(
+    mut collisions: EventReader<CollisionStarted>,
+    ball: Query<(), With<Ball>>,
+    goal: Query<(), With<Goal>>,
+    mut ball_velocity: Query<(&mut LinearVelocity, &mut AngularVelocity), With<Ball>>,
+    mut run_timer: ResMut<RunTimer>,
+    mut solved: EventWriter<MazeSolved>,
+) -> Result {
+    for &CollisionStarted(a, b) in collisions.read() {
+        let reached_goal = (ball.contains(a) && goal.contains(b))
+            || (ball.contains(b) && goal.contains(a));
+        if !reached_goal || !run_timer.running {
+            continue;
+        }
+
+        run_timer.running = false;
+        solved.write(MazeSolved {
+            secs: run_timer.elapsed_secs,
+        });
+
+        // Stop the ball at the goal. This zeroes its velocities once; it stays a dynamic
+        // body, so gravity and the board's tilt move it again from the next physics step.
+        let (mut linear, mut angular) = ball_velocity.single_mut()?;
+        *linear = LinearVelocity::ZERO;
+        *angular = AngularVelocity::ZERO;
+    }
+    Ok(())
+}
+
+/// Which local gamepad drives which input, so couch co-op pads don't fight over the
+/// same maze/camera: the first pad to press `Start` while unassigned claims
+/// [`PlayerAssignment::tilt`] and drives `maze_attitude`; the second claims
+/// [`PlayerAssignment::camera`] and drives `adjust_camera`'s dolly/zoom. `reset_ball`
+/// stays unrestricted, since resetting the attempt isn't a role-specific action.
+#[derive(Resource, Default)]
+pub struct PlayerAssignment {
+    tilt: Option<Entity>,
+    camera: Option<Entity>,
+}
+
+impl PlayerAssignment {
+    fn is_assigned(&self, gamepad: Entity) -> bool {
+        self.tilt == Some(gamepad) || self.camera == Some(gamepad)
+    }
+
+    /// The gamepad that should drive `adjust_camera`: [`PlayerAssignment::camera`] once
+    /// a second pad has claimed it, or else [`PlayerAssignment::tilt`], so a single
+    /// connected pad still controls dolly/zoom instead of the camera going dead until
+    /// a second player joins.
+    fn camera_gamepad(&self) -> Option<Entity> {
+        self.camera.or(self.tilt)
+    }
+}
+
+fn assign_players(gamepads: Query<(Entity, &Gamepad)>, mut assignment: ResMut<PlayerAssignment>) {
+    for (entity, gp) in &gamepads {
+        if !gp.just_pressed(GamepadButton::Start) || assignment.is_assigned(entity) {
+            continue;
+        }
+        if assignment.tilt.is_none() {
+            assignment.tilt = Some(entity);
+        } else if assignment.camera.is_none() {
+            assignment.camera = Some(entity);
+        }
+    }
+}
+
+pub(crate) const ANALOG_THRESHOLD: f32 = 0.1;
 
-fn adjust_camera(
-    gamepads: Query<&Gamepad>,
+pub(crate) fn adjust_camera(
+    gamepads: Query<(Entity, &Gamepad)>,
+    assignment: Res<PlayerAssignment>,
     mut camera: Query<(&mut Transform, &mut Projection), With<Camera>>,
     time: Res<Time>,
 ) -> Result {
-    for gp in gamepads {
+    for (entity, gp) in gamepads {
+        if assignment.camera_gamepad() != Some(entity) {
+            continue;
+        }
         let (mut transform, projection) = camera.single_mut()?;
         let analog = gp.analog();
 
@@ -251,40 +621,126 @@ fn adjust_camera(
     Ok(())
 }
 
-fn maze_attitude(
-    gamepads: Query<&Gamepad, Changed<Gamepad>>,
+/// Maximum angle the maze's up vector may tilt away from [`MAZE_NEUTRAL_UP`], like a
+/// wooden marble labyrinth that can't be tipped past a shallow angle.
+pub(crate) const MAX_TILT_RAD: f32 = FRAC_PI_6;
+
+/// The maze's up vector at rest. `setup` spawns the `Maze` rotated
+/// `FRAC_PI_2` around world X so the board lies flat to be rolled on, which makes its
+/// neutral up vector world `+Z` rather than `Vec3::Y`; [`MAX_TILT_RAD`] is measured
+/// from this, not from `Vec3::Y`.
+pub(crate) const MAZE_NEUTRAL_UP: Vec3 = Vec3::Z;
+
+pub(crate) fn maze_attitude(
+    gamepads: Query<(Entity, &Gamepad), Changed<Gamepad>>,
+    assignment: Res<PlayerAssignment>,
     mut maze: Query<(&mut AngularVelocity, &Transform), With<Maze>>,
 ) -> Result {
     const MAX_ATTITUDE_DELTA_RAD_PER_SEC: f32 = FRAC_PI_2;
-    for gp in gamepads {
-        if let Some(rotation) = gp.analog().get(GamepadAxis::LeftStickX) {
-            let (mut angular, transform) = maze.single_mut()?;
-            *angular = AngularVelocity(if rotation.abs() > ANALOG_THRESHOLD {
-                -transform.up() * rotation * MAX_ATTITUDE_DELTA_RAD_PER_SEC
-            } else {
-                Vec3::ZERO
-            });
+    for (entity, gp) in gamepads {
+        if assignment.tilt != Some(entity) {
+            continue;
         }
+        let analog = gp.analog();
+        let stick_x = analog.get(GamepadAxis::LeftStickX).unwrap_or_default();
+        let stick_y = analog.get(GamepadAxis::LeftStickY).unwrap_or_default();
+
+        let (mut angular, transform) = maze.single_mut()?;
+
+        // Pitch around world X from the stick's Y axis, roll around world Y from its
+        // X axis, so the stick tilts the board toward itself rather than spinning it.
+        let pitch = if stick_y.abs() > ANALOG_THRESHOLD {
+            stick_y * MAX_ATTITUDE_DELTA_RAD_PER_SEC
+        } else {
+            0.
+        };
+        let roll = if stick_x.abs() > ANALOG_THRESHOLD {
+            -stick_x * MAX_ATTITUDE_DELTA_RAD_PER_SEC
+        } else {
+            0.
+        };
+
+        *angular = AngularVelocity(clamped_attitude(pitch, roll, *transform.up()));
     }
     Ok(())
 }
 
+/// Turns a pitch/roll input pair into the maze's target [`AngularVelocity`], clamped so
+/// the stick can't tip `up` past [`MAX_TILT_RAD`] away from [`MAZE_NEUTRAL_UP`] (though
+/// it can still bring an already-overtilted board back toward level). `pitch` is an
+/// angular rate around world X, `roll` around world Y, matching how `setup` lays the
+/// board flat with its rest-state up vector on world `+Z`. Shared by [`maze_attitude`]
+/// and [`net::netplay_tilt`] so the two local/netplay tilt paths can't drift apart.
+pub(crate) fn clamped_attitude(pitch: f32, roll: f32, up: Vec3) -> Vec3 {
+    let mut target = Vec3::new(pitch, roll, 0.);
+
+    if up.angle_between(MAZE_NEUTRAL_UP) >= MAX_TILT_RAD {
+        // Already at the limit: only let a component through if it's rotating `up`
+        // back toward level rather than further away. A rotation around `axis` moves
+        // `up` by `axis.cross(up)`; it's pushing outward when that motion points the
+        // same way as `up`'s own displacement from neutral.
+        let outward = up - up.dot(MAZE_NEUTRAL_UP) * MAZE_NEUTRAL_UP;
+        let pushes_outward = |axis: Vec3, component: f32| {
+            component != 0. && axis.cross(up).dot(outward) * component > 0.
+        };
+        if pushes_outward(Vec3::X, target.x) {
+            target.x = 0.;
+        }
+        if pushes_outward(Vec3::Y, target.y) {
+            target.y = 0.;
+        }
+    }
+
+    target
+}
+
 fn is_in_bounds(delta: f32, current: f32, min: f32, max: f32) -> bool {
     (delta.is_sign_positive() && f32::inverse_lerp(max, min, current).is_sign_positive())
         || (delta.is_sign_negative() && f32::inverse_lerp(min, max, current).is_sign_positive())
 }
 
-fn reset_ball(
+pub(crate) fn reset_ball(
     gamepads: Query<&Gamepad, Changed<Gamepad>>,
     mut ball: Query<(&mut Transform, &mut LinearVelocity), With<Ball>>,
     start: Query<&GlobalTransform, (With<BallStart>, Without<Ball>)>,
+    mut run_timer: ResMut<RunTimer>,
 ) -> Result {
     for gp in gamepads {
         if gp.just_pressed(GamepadButton::Start) {
             let (mut transform, mut velocity) = ball.single_mut()?;
             transform.translation = start.single()?.translation();
             *velocity = LinearVelocity::ZERO;
+            run_timer.elapsed_secs = 0.;
+            run_timer.running = true;
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::generate_maze;
+
+    #[test]
+    fn generate_maze_matches_setups_invariants() {
+        for (cells_w, cells_h) in [(1, 1), (3, 5), (6, 6), (9, 4)] {
+            let maze = generate_maze(cells_w, cells_h, 42);
+
+            assert_eq!(maze.len(), (2 * cells_h + 1) as usize);
+            assert_eq!(maze.len() % 2, 1);
+            for row in &maze {
+                assert_eq!(row.chars().count(), (2 * cells_w + 1) as usize);
+            }
+
+            let start_count: usize = maze.iter().map(|row| row.matches('x').count()).sum();
+            assert_eq!(start_count, 1, "maze should have exactly one start cell");
+
+            let goal_count: usize = maze.iter().map(|row| row.matches('o').count()).sum();
+            if (cells_w, cells_h) == (1, 1) {
+                assert_eq!(goal_count, 0, "single-cell maze has nowhere to put a goal");
+            } else {
+                assert_eq!(goal_count, 1, "maze should have exactly one goal cell");
+            }
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! Optional two-player netplay: each player's left stick drives one tilt axis of the
+//! same board, kept in sync over a plain UDP socket with GGRS-style rollback so play
+//! stays smooth despite network latency.
+//!
+//! The whole mode hinges on the simulation being deterministic: [`plugin`] composes
+//! with [`crate::plugin`] for `DefaultPlugins`, `PhysicsPlugins` and `setup` (the same
+//! way `character::plugin` does) rather than rebuilding them, and `avian3d` already
+//! steps physics in `FixedPostUpdate` off the generic [`Time<Fixed>`] clock, so
+//! inserting [`Time::<Fixed>::from_hz(ROLLBACK_HZ)`] here is what makes every peer's
+//! `avian3d` step with the same `delta_secs`. The maze's RNG seed is agreed at session
+//! start via [`NetConfig::seed`] rather than sampled per-frame, so both peers generate
+//! the identical maze.
+//!
+//! [`plugin`] inserts [`NetplayMode`] and drives its own tilt system plus
+//! `spawn_ball`/`reset_ball` inside [`FixedUpdate`] alongside rollback bookkeeping;
+//! [`NetplayMode`] gates off [`crate::plugin`]'s `Update`-scheduled copies of those
+//! three systems so the two don't fight over the same `Maze`/`Ball` every tick.
+
+use std::net::{SocketAddr, UdpSocket};
+
+use avian3d::dynamics::rigid_body::{AngularVelocity, LinearVelocity};
+use bevy::{
+    app::{App, FixedUpdate},
+    ecs::{
+        error::Result,
+        query::{With, Without},
+        resource::Resource,
+        schedule::IntoScheduleConfigs,
+        system::{Query, Res, ResMut},
+    },
+    input::gamepad::{Gamepad, GamepadAxis},
+    time::{Fixed, Time},
+    transform::components::Transform,
+};
+use bytemuck::{Pod, Zeroable};
+
+use crate::{ANALOG_THRESHOLD, Ball, Maze, MazeConfig};
+
+/// Rollback netplay steps the simulation at a fixed rate so both peers agree on
+/// `delta_secs` regardless of their real frame rate.
+pub const ROLLBACK_HZ: f64 = 60.;
+
+/// How many simulation frames a locally-sampled input is held before being applied,
+/// giving the remote input time to arrive before it's needed.
+const INPUT_DELAY_FRAMES: u32 = 2;
+
+/// How far ahead of the last confirmed frame the simulation is allowed to predict
+/// before rollback is required to catch up.
+const MAX_PREDICTION_FRAMES: u32 = 8;
+
+/// One player's quantized input for a single simulation frame. `Pod`/`Zeroable` so it
+/// round-trips over the wire as raw bytes with no serialization step.
+#[derive(Clone, Copy, Default, Pod, Zeroable)]
+#[repr(C)]
+pub struct NetInput {
+    pub stick_x: i8,
+    pub stick_y: i8,
+    pub buttons: u8,
+    _pad: u8,
+}
+
+impl NetInput {
+    fn sample(gamepad: &Gamepad) -> Self {
+        let analog = gamepad.analog();
+        let quantize = |v: f32| (v.clamp(-1., 1.) * i8::MAX as f32) as i8;
+        Self {
+            stick_x: quantize(analog.get(GamepadAxis::LeftStickX).unwrap_or_default()),
+            stick_y: quantize(analog.get(GamepadAxis::LeftStickY).unwrap_or_default()),
+            buttons: 0,
+            _pad: 0,
+        }
+    }
+
+    pub fn stick_x(&self) -> f32 {
+        self.stick_x as f32 / i8::MAX as f32
+    }
+
+    pub fn stick_y(&self) -> f32 {
+        self.stick_y as f32 / i8::MAX as f32
+    }
+}
+
+/// Session configuration for a netplay match, inserted before [`plugin`] runs.
+#[derive(Resource, Clone)]
+pub struct NetConfig {
+    pub local_addr: SocketAddr,
+    pub remote_addr: SocketAddr,
+    /// `0` rolls the board around world Y with its stick X, `1` pitches it around world X
+    /// with its stick Y; each peer owns one axis.
+    pub local_player: u8,
+    /// Maze RNG seed agreed by both peers before the match starts.
+    pub seed: u64,
+}
+
+#[derive(Resource)]
+struct NetSocket(UdpSocket);
+
+/// A confirmed or predicted input for both players on a given simulation frame.
+#[derive(Clone, Copy, Default)]
+struct FrameInputs {
+    players: [NetInput; 2],
+    /// Bitmask of which `players` slots are confirmed (vs. predicted by defaulting to
+    /// a neutral, no-input `NetInput` until the real one arrives).
+    confirmed: u8,
+}
+
+/// Ring buffer of per-frame inputs and world snapshots, indexed by
+/// `frame % MAX_PREDICTION_FRAMES`.
+#[derive(Resource)]
+struct RollbackState {
+    frame: u32,
+    /// The newest frame a remote input has actually been received for. `record_snapshot`
+    /// won't let `frame + INPUT_DELAY_FRAMES` (the frame local input is written for) run
+    /// more than [`MAX_PREDICTION_FRAMES`] ahead of this, so a
+    /// stalled peer pauses local advance instead of recycling ring-buffer slots the
+    /// remote hasn't caught up to yet.
+    last_confirmed_remote_frame: u32,
+    /// Set by [`receive_remote_input`] when a late remote input contradicts what was
+    /// predicted for an already-simulated frame; consumed by [`apply_rollback`].
+    pending_rollback_to: Option<u32>,
+    inputs: Vec<FrameInputs>,
+    /// The frame each `inputs` slot currently holds data for, so a slot recycled from
+    /// `MAX_PREDICTION_FRAMES` cycles ago can be told apart from one already populated
+    /// for the frame being asked about (see [`RollbackState::ensure_slot`]).
+    input_frames: Vec<Option<u32>>,
+    snapshots: Vec<WorldSnapshot>,
+}
+
+impl Default for RollbackState {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            last_confirmed_remote_frame: 0,
+            pending_rollback_to: None,
+            inputs: vec![FrameInputs::default(); MAX_PREDICTION_FRAMES as usize],
+            input_frames: vec![None; MAX_PREDICTION_FRAMES as usize],
+            snapshots: vec![WorldSnapshot::default(); MAX_PREDICTION_FRAMES as usize],
+        }
+    }
+}
+
+impl RollbackState {
+    fn slot(frame: u32) -> usize {
+        (frame % MAX_PREDICTION_FRAMES) as usize
+    }
+
+    /// The slot for `frame`, clearing out whatever an older frame (recycled
+    /// `MAX_PREDICTION_FRAMES` cycles ago) left behind so its confirmed bits and
+    /// player inputs can't be mistaken for this frame's data.
+    fn ensure_slot(&mut self, frame: u32) -> usize {
+        let slot = Self::slot(frame);
+        if self.input_frames[slot] != Some(frame) {
+            self.inputs[slot] = FrameInputs::default();
+            self.input_frames[slot] = Some(frame);
+        }
+        slot
+    }
+}
+
+/// Everything needed to restore the simulation to an earlier frame: the `Ball`'s
+/// transform and velocities, and the `Maze`'s rotation and angular velocity.
+#[derive(Clone, Copy, Default)]
+struct WorldSnapshot {
+    ball_transform: Transform,
+    ball_linear_velocity: LinearVelocity,
+    ball_angular_velocity: AngularVelocity,
+    maze_rotation: Transform,
+    maze_angular_velocity: AngularVelocity,
+}
+
+/// Inserted by [`plugin`] so `crate::maze_attitude`, `crate::reset_ball` and
+/// `crate::spawn_ball`'s `Update`-scheduled copies stand down in favor of this
+/// module's own `FixedUpdate` chain.
+#[derive(Resource)]
+pub(crate) struct NetplayMode;
+
+pub fn plugin(app: &mut App, config: NetConfig) {
+    let socket = UdpSocket::bind(config.local_addr).expect("bind netplay socket");
+    socket.set_nonblocking(true).expect("non-blocking socket");
+    socket.connect(config.remote_addr).expect("connect to peer");
+
+    // Both peers need the same maze, so the agreed seed has to land in `MazeConfig`
+    // before `crate::plugin`'s `Startup` system reads it; `init_resource` there is a
+    // no-op once the resource already exists.
+    app.insert_resource(MazeConfig {
+        seed: config.seed,
+        ..MazeConfig::default()
+    })
+    .add_plugins(crate::plugin)
+    .insert_resource(NetplayMode)
+    .insert_resource(config)
+    .insert_resource(NetSocket(socket))
+    .insert_resource(RollbackState::default())
+    .insert_resource(Time::<Fixed>::from_hz(ROLLBACK_HZ))
+    .add_systems(
+        FixedUpdate,
+        (
+            receive_remote_input,
+            apply_rollback,
+            sample_and_send_local_input,
+            netplay_tilt,
+            crate::spawn_ball,
+            crate::reset_ball,
+            record_snapshot,
+        )
+            .chain(),
+    );
+}
+
+/// Tilts the board from both players' synchronized inputs instead of
+/// `crate::maze_attitude`'s single local gamepad: player 0's stick X drives roll,
+/// player 1's stick Y drives pitch, clamped by the same [`crate::clamped_attitude`]
+/// `maze_attitude` uses, so the two tilt paths can't drift apart.
+fn netplay_tilt(
+    rollback: Res<RollbackState>,
+    mut maze: Query<(&mut AngularVelocity, &Transform), With<Maze>>,
+) -> Result {
+    const MAX_ATTITUDE_DELTA_RAD_PER_SEC: f32 = std::f32::consts::FRAC_PI_2;
+
+    let roll_input = frame_input(&rollback, 0);
+    let pitch_input = frame_input(&rollback, 1);
+
+    let (mut angular, transform) = maze.single_mut()?;
+
+    let roll = if roll_input.stick_x().abs() > ANALOG_THRESHOLD {
+        -roll_input.stick_x() * MAX_ATTITUDE_DELTA_RAD_PER_SEC
+    } else {
+        0.
+    };
+    let pitch = if pitch_input.stick_y().abs() > ANALOG_THRESHOLD {
+        pitch_input.stick_y() * MAX_ATTITUDE_DELTA_RAD_PER_SEC
+    } else {
+        0.
+    };
+
+    *angular = AngularVelocity(crate::clamped_attitude(pitch, roll, *transform.up()));
+    Ok(())
+}
+
+fn sample_and_send_local_input(
+    config: Res<NetConfig>,
+    socket: Res<NetSocket>,
+    gamepads: Query<&Gamepad>,
+    mut rollback: ResMut<RollbackState>,
+) {
+    let input = gamepads
+        .iter()
+        .next()
+        .map(NetInput::sample)
+        .unwrap_or_default();
+
+    let target_frame = rollback.frame + INPUT_DELAY_FRAMES;
+    let slot = rollback.ensure_slot(target_frame);
+    rollback.inputs[slot].players[config.local_player as usize] = input;
+    rollback.inputs[slot].confirmed |= 1 << config.local_player;
+
+    let mut packet = [0u8; 8];
+    packet[0..4].copy_from_slice(&target_frame.to_le_bytes());
+    packet[4..8].copy_from_slice(bytemuck::bytes_of(&input));
+    let _ = socket.0.send(&packet);
+}
+
+fn receive_remote_input(
+    config: Res<NetConfig>,
+    socket: Res<NetSocket>,
+    mut rollback: ResMut<RollbackState>,
+) {
+    let remote_player = 1 - config.local_player;
+    let mut packet = [0u8; 8];
+    while let Ok(len) = socket.0.recv(&mut packet) {
+        if len < 8 {
+            continue;
+        }
+        let frame = u32::from_le_bytes(packet[0..4].try_into().unwrap());
+        let input: NetInput = *bytemuck::from_bytes(&packet[4..8]);
+
+        // A frame this far behind has had its ring-buffer slot recycled for a newer
+        // frame already; applying it now would read/write the wrong frame's data.
+        if frame + MAX_PREDICTION_FRAMES <= rollback.frame {
+            continue;
+        }
+
+        rollback.last_confirmed_remote_frame = rollback.last_confirmed_remote_frame.max(frame);
+
+        let slot = rollback.ensure_slot(frame);
+        let predicted = rollback.inputs[slot].players[remote_player as usize];
+        rollback.inputs[slot].players[remote_player as usize] = input;
+        rollback.inputs[slot].confirmed |= 1 << remote_player;
+
+        let predicted_wrong = predicted.stick_x != input.stick_x || predicted.stick_y != input.stick_y;
+        if frame < rollback.frame && predicted_wrong {
+            // The frame we already simulated used a guessed remote input that turned
+            // out wrong: roll back to it so `apply_rollback` can restore the snapshot
+            // taken before it and re-simulate forward with the correction.
+            rollback.pending_rollback_to = Some(
+                rollback
+                    .pending_rollback_to
+                    .map_or(frame, |existing| existing.min(frame)),
+            );
+        }
+    }
+}
+
+/// When [`receive_remote_input`] flagged a misprediction, restores the `Ball` and
+/// `Maze` to the snapshot recorded for that frame and rewinds `rollback.frame` to
+/// match. The remaining systems in this same tick (and the following ticks, since
+/// `rollback.frame` is now behind the wall clock) then naturally re-simulate forward
+/// using the corrected input, which is how the rollback "catches up".
+#[allow(clippy::type_complexity)]
+fn apply_rollback(
+    mut rollback: ResMut<RollbackState>,
+    mut ball: Query<(&mut Transform, &mut LinearVelocity, &mut AngularVelocity), With<Ball>>,
+    mut maze: Query<(&mut Transform, &mut AngularVelocity), (With<Maze>, Without<Ball>)>,
+) -> Result {
+    let Some(rollback_to) = rollback.pending_rollback_to.take() else {
+        return Ok(());
+    };
+
+    let snapshot = rollback.snapshots[RollbackState::slot(rollback_to)];
+    let (mut ball_transform, mut ball_linear, mut ball_angular) = ball.single_mut()?;
+    *ball_transform = snapshot.ball_transform;
+    *ball_linear = snapshot.ball_linear_velocity;
+    *ball_angular = snapshot.ball_angular_velocity;
+
+    let (mut maze_transform, mut maze_angular) = maze.single_mut()?;
+    *maze_transform = snapshot.maze_rotation;
+    *maze_angular = snapshot.maze_angular_velocity;
+
+    rollback.frame = rollback_to;
+    Ok(())
+}
+
+#[allow(clippy::type_complexity)]
+fn record_snapshot(
+    mut rollback: ResMut<RollbackState>,
+    ball: Query<(&Transform, &LinearVelocity, &AngularVelocity), With<Ball>>,
+    maze: Query<(&Transform, &AngularVelocity), (With<Maze>, Without<Ball>)>,
+) -> Result {
+    let (ball_transform, ball_linear_velocity, ball_angular_velocity) = ball.single()?;
+    let (maze_rotation, maze_angular_velocity) = maze.single()?;
+    let frame = rollback.frame;
+    rollback.snapshots[RollbackState::slot(frame)] = WorldSnapshot {
+        ball_transform: *ball_transform,
+        ball_linear_velocity: *ball_linear_velocity,
+        ball_angular_velocity: *ball_angular_velocity,
+        maze_rotation: *maze_rotation,
+        maze_angular_velocity: *maze_angular_velocity,
+    };
+    // Don't let the input-delayed frame local input is written for get further than
+    // MAX_PREDICTION_FRAMES past the last frame the remote has actually confirmed input
+    // for, or a stalled peer would let local advance recycle ring-buffer slots the
+    // eventual remote input still needs.
+    if rollback.frame + INPUT_DELAY_FRAMES
+        < rollback.last_confirmed_remote_frame + MAX_PREDICTION_FRAMES
+    {
+        rollback.frame += 1;
+    }
+    Ok(())
+}
+
+/// Reads the confirmed-or-predicted [`NetInput`] for `player` on the current rollback
+/// frame, or a neutral default if nothing has been recorded for it yet (rather than
+/// whatever older frame's data the ring-buffer slot last held). [`netplay_tilt`] uses
+/// this instead of reading gamepads directly, so the tilt stays deterministic across
+/// both peers.
+fn frame_input(rollback: &RollbackState, player: u8) -> NetInput {
+    let slot = RollbackState::slot(rollback.frame);
+    if rollback.input_frames[slot] != Some(rollback.frame) {
+        return NetInput::default();
+    }
+    rollback.inputs[slot].players[player as usize]
+}